@@ -4,7 +4,10 @@ use nqueens::NQueens;
 pub fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("nqueens 10", |b| {
         b.iter(|| {
-            let mut nqueen = NQueens::new(black_box(10)).unwrap().into_random_state();
+            let mut nqueen = NQueens::new(black_box(10))
+                .unwrap()
+                .with_seed(42)
+                .into_random_state();
             while nqueen.step() != 0 {}
         })
     });
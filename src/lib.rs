@@ -1,5 +1,7 @@
-use rand::{seq::IteratorRandom, Rng};
+use rand::{rngs::StdRng, seq::IteratorRandom, Rng, SeedableRng};
+use std::borrow::Cow;
 use std::collections::HashSet;
+use std::time::{Duration, Instant};
 
 /// Máquina de estado de un problema de N-Reinas
 ///
@@ -10,76 +12,525 @@ pub struct NQueens {
     n: usize,
     queens: Vec<usize>,
 
+    // Contadores incrementales de conflictos: cuántas reinas hay en cada
+    // columna y en cada una de las dos familias de diagonales. Mantenerlos
+    // actualizados en O(1) por movimiento evita recalcular el tablero entero
+    // (O(n)) cada vez que se evalúa el costo de una reina.
+    col_count: Vec<usize>,
+    /// Diagonal "\": constante por `fila - columna` (offset por `n - 1` para no ser negativo).
+    diag_minus: Vec<usize>,
+    /// Diagonal "/": constante por `fila + columna`.
+    diag_plus: Vec<usize>,
+
     last_queens: HashSet<Vec<usize>>,
     costs: Vec<(usize, usize, usize)>,
     verbose: bool,
+    tie_strategy: TieStrategy,
+    seed: Option<u64>,
+    rng: StdRng,
+    plateau_limit: usize,
+
+    // Estado de la búsqueda tabú (`step_tabu`).
+    /// `tabu[reina][columna]` guarda la iteración hasta la cual esa
+    /// reasignación está prohibida (0 si nunca se ha prohibido).
+    tabu: Vec<Vec<usize>>,
+    tabu_tenure: usize,
+    tabu_iter: usize,
+    best_board: Vec<usize>,
+    best_cost: usize,
+
+    /// `true` si `self.queens` es una permutación de `0..n` (ver
+    /// `into_random_permutation`/`step_swap`), en cuyo caso los conflictos de
+    /// columna son cero por construcción y `Display` no los reporta.
+    ///
+    /// Solo `into_random_permutation`/`step_swap` mantienen esta garantía;
+    /// `randomize`, `with_state`, `step`, `step_tabu` y `anneal` la apagan al
+    /// entrar porque pueden dejar dos reinas en la misma columna.
+    permutation_mode: bool,
+}
+
+/// Resultado de correr [`NQueens::run`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SolveOutcome {
+    /// Se encontró una solución (costo total 0).
+    Solved { iterations: usize, restarts: usize },
+    /// Se agotaron las iteraciones o los restarts disponibles sin resolverlo.
+    GaveUp {
+        best_cost: usize,
+        iterations: usize,
+        restarts: usize,
+    },
+}
+
+/// Resultado de correr [`NQueens::solve`].
+///
+/// A diferencia de [`SolveOutcome`], que solo dice si se resolvió o no,
+/// `SolveReport` también incluye el tablero final, para poder compararlo
+/// directamente contra otros modos de búsqueda (`step_tabu`, `anneal`) sin
+/// que el llamador tenga que leer `self.queens` por su cuenta.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolveReport {
+    /// `true` si se encontró una solución (costo total 0).
+    pub solved: bool,
+    /// Tablero final, en la misma representación columna-por-fila que el resto del crate.
+    pub board: Vec<usize>,
+    /// Iteraciones de `step` realizadas.
+    pub iterations: usize,
+    /// Reinicios aleatorios aplicados.
+    pub restarts: usize,
+    /// Mejor costo alcanzado (0 si `solved` es `true`).
+    pub best_cost: usize,
+}
+
+/// Estrategia usada para desempatar cuando varios candidatos
+/// (reinas o columnas) comparten el mismo costo.
+///
+/// `step` se apoya en esta estrategia en los dos puntos donde antes
+/// escogía aleatoriamente entre empatados: al elegir la reina más cara
+/// y al elegir la columna que más reduce su costo.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TieStrategy {
+    /// Toma el candidato con el índice más bajo.
+    First,
+    /// Toma el candidato con el índice más alto.
+    Last,
+    /// Escoge uno al azar entre los empatados (comportamiento histórico).
+    Random,
+    /// Muestra los candidatos empatados y pide al usuario elegir uno por stdin.
+    Prompt,
 }
 
-enum Side {
-    Left,
-    Right,
+/// Presupuesto de terminación para [`NQueens::anneal`].
+#[derive(Clone, Copy, Debug)]
+pub enum AnnealingBudget {
+    /// Se detiene tras este número de iteraciones.
+    Iterations(usize),
+    /// Se detiene al agotarse este tiempo de reloj.
+    Time(Duration),
+}
+
+/// Configuración de temple simulado (simulated annealing) para [`NQueens::anneal`].
+///
+/// A diferencia de `step`/`step_tabu`, que siempre se mueven hacia el mejor
+/// vecino, `anneal` también acepta movimientos que empeoran el costo con
+/// probabilidad `exp(-delta/t)`, lo que le permite escapar de mínimos locales
+/// donde la heurística determinista se estanca.
+#[derive(Clone, Debug)]
+pub struct SimulatedAnnealing {
+    /// Temperatura inicial.
+    pub t0: f64,
+    /// Factor de enfriamiento geométrico aplicado a `t` en cada iteración (p. ej. 0.995).
+    pub alpha: f64,
+    /// Presupuesto de iteraciones o de tiempo de reloj.
+    pub budget: AnnealingBudget,
+}
+
+/// Problema de búsqueda local genérico: sabe generar vecinos de un estado y
+/// costearlo, pero no sabe nada de reinicios ni de cómo recorrer el espacio
+/// de búsqueda. Eso lo provee [`LocalSearchEngine`].
+///
+/// Generaliza la pareja `neighbors`/`cost` que antes vivía atada a
+/// `NQueens::step`, para que el mismo motor de ascenso de colina con
+/// reinicios por meseta funcione sobre cualquier problema de restricciones
+/// representable como un estado con vecinos (coloreo de grafos, asignación, etc.).
+pub trait LocalSearchProblem {
+    /// Representación completa de un estado del problema.
+    ///
+    /// Se exige `Eq + Hash` además de `Clone` porque [`LocalSearchEngine`]
+    /// mantiene un conjunto de estados ya vistos para detectar ciclos, igual
+    /// que `last_queens` hace para `NQueens::step`.
+    type State: Clone + Eq + std::hash::Hash;
+
+    /// Todos los estados alcanzables desde `state` en un solo movimiento.
+    fn neighbors(&self, state: &Self::State) -> Vec<Self::State>;
+
+    /// Costo de `state`, es decir, número de restricciones violadas. Cero
+    /// significa que `state` es una solución válida.
+    fn cost(&self, state: &Self::State) -> usize;
+
+    /// Un estado inicial aleatorio, usado por [`LocalSearchEngine`] al reiniciar.
+    fn random_state(&self, rng: &mut StdRng) -> Self::State;
+}
+
+/// Motor de ascenso de colina con reinicios por meseta, genérico sobre
+/// cualquier [`LocalSearchProblem`].
+///
+/// Generaliza la política de reinicio que [`NQueens::run`] aplica sobre
+/// `step`: en cada iteración se mueve al vecino de menor costo; tras
+/// `plateau_limit` iteraciones sin mejora estricta se reinicia desde un
+/// estado aleatorio nuevo. A diferencia de `step`, que usa `self.tie_strategy`
+/// para desempatar, aquí se toma simplemente el primer vecino de costo
+/// mínimo, ya que el motor no conoce la noción de "reina" ni de columna.
+pub struct LocalSearchEngine<P: LocalSearchProblem> {
+    problem: P,
+    plateau_limit: usize,
+    rng: StdRng,
+    /// Estados ya visitados desde el último reinicio, igual que
+    /// `NQueens::last_queens`: si el mejor vecino ya fue visto, el ascenso de
+    /// colina está dando vueltas en un ciclo y conviene reiniciar.
+    seen: HashSet<P::State>,
+}
+
+impl<P: LocalSearchProblem> LocalSearchEngine<P> {
+    /// Crea un motor para `problem`, reiniciando tras `plateau_limit`
+    /// iteraciones sin mejora.
+    pub fn new(problem: P, plateau_limit: usize) -> Self {
+        LocalSearchEngine {
+            problem,
+            plateau_limit,
+            rng: StdRng::from_entropy(),
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Fija la semilla usada para los reinicios aleatorios del motor.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// El vecino de menor costo de `state`, y ese costo.
+    fn best_neighbor(&self, state: &P::State) -> (P::State, usize) {
+        self.problem
+            .neighbors(state)
+            .into_iter()
+            .map(|s| {
+                let cost = self.problem.cost(&s);
+                (s, cost)
+            })
+            .min_by_key(|(_, cost)| *cost)
+            .expect("neighbors() debe devolver al menos un estado")
+    }
+
+    /// Corre el motor desde `initial_state` hasta encontrar una solución
+    /// (costo 0) o agotar `max_iterations`/`max_restarts`, igual que
+    /// `NQueens::run` pero sobre cualquier `LocalSearchProblem`.
+    pub fn run(
+        &mut self,
+        initial_state: P::State,
+        max_iterations: usize,
+        max_restarts: usize,
+    ) -> SolveOutcome {
+        let mut state = initial_state;
+        let mut cost = self.problem.cost(&state);
+        let mut best_cost = cost;
+        let mut since_improvement = 0;
+        let mut iterations = 0;
+        let mut restarts = 0;
+        self.seen.clear();
+        self.seen.insert(state.clone());
+
+        while iterations < max_iterations {
+            if cost == 0 {
+                return SolveOutcome::Solved {
+                    iterations,
+                    restarts,
+                };
+            }
+
+            let (next_state, next_cost) = self.best_neighbor(&state);
+            let is_cycle = self.seen.contains(&next_state);
+            state = next_state;
+            cost = next_cost;
+            iterations += 1;
+
+            if cost < best_cost {
+                best_cost = cost;
+                since_improvement = 0;
+            } else {
+                since_improvement += 1;
+            }
+
+            // Igual que `NQueens::step` con `last_queens`: si el mejor vecino
+            // ya fue visitado, el ascenso de colina está dando vueltas en un
+            // ciclo, así que forzamos el reinicio en vez de esperar a que se
+            // agote `plateau_limit`.
+            if is_cycle && since_improvement < self.plateau_limit {
+                since_improvement = self.plateau_limit;
+            } else {
+                self.seen.insert(state.clone());
+            }
+
+            if since_improvement >= self.plateau_limit {
+                if restarts >= max_restarts {
+                    break;
+                }
+                state = self.problem.random_state(&mut self.rng);
+                cost = self.problem.cost(&state);
+                restarts += 1;
+                since_improvement = 0;
+                self.seen.clear();
+                self.seen.insert(state.clone());
+            }
+        }
+
+        if cost == 0 {
+            SolveOutcome::Solved {
+                iterations,
+                restarts,
+            }
+        } else {
+            SolveOutcome::GaveUp {
+                best_cost,
+                iterations,
+                restarts,
+            }
+        }
+    }
+}
+
+/// Lee un valor de stdin, mostrando `msg`, reutilizando `buff` como buffer
+/// de entrada y transformando la línea leída con `transformer`.
+fn pedir_valor<V, F: Fn(&str) -> Result<V, Cow<'static, str>>>(
+    msg: &'static str,
+    buff: &mut String,
+    transformer: F,
+) -> Result<V, Cow<'static, str>> {
+    use std::io::Write;
+    use std::io::{stdin, stdout};
+
+    let mut out = stdout().lock();
+    write!(out, "{msg}").unwrap();
+    out.flush().unwrap();
+
+    buff.clear();
+    let input = match stdin().read_line(buff) {
+        Ok(n) if n > 0 => buff,
+        Ok(_) => {
+            return Err("No input provided, read 0 bytes from stdin"
+                .to_string()
+                .into())
+        }
+        Err(err) => return Err(format!("Error while reading stdin: {err}").into()),
+    };
+
+    transformer(input.trim())
+}
+
+/// Estado de ocupación (columna y ambas diagonales) usado por
+/// `NQueens::backtrack`. Agrupa los tres arreglos booleanos en un solo valor
+/// para no pasarlos como parámetros sueltos (`clippy::too_many_arguments`).
+struct Occupancy {
+    n: usize,
+    cols: Vec<bool>,
+    diag_minus: Vec<bool>,
+    diag_plus: Vec<bool>,
+}
+
+impl Occupancy {
+    fn new(n: usize) -> Self {
+        Occupancy {
+            n,
+            cols: vec![false; n],
+            diag_minus: vec![false; 2 * n - 1],
+            diag_plus: vec![false; 2 * n - 1],
+        }
+    }
+
+    fn is_occupied(&self, row: usize, col: usize) -> bool {
+        self.cols[col]
+            || self.diag_minus[row + self.n - 1 - col]
+            || self.diag_plus[row + col]
+    }
+
+    fn occupy(&mut self, row: usize, col: usize) {
+        self.cols[col] = true;
+        self.diag_minus[row + self.n - 1 - col] = true;
+        self.diag_plus[row + col] = true;
+    }
+
+    fn free(&mut self, row: usize, col: usize) {
+        self.cols[col] = false;
+        self.diag_minus[row + self.n - 1 - col] = false;
+        self.diag_plus[row + col] = false;
+    }
 }
 
 impl NQueens {
+    /// Índice en `diag_minus` de la diagonal "\" que pasa por `(row, col)`.
+    fn diag_minus_idx(&self, row: usize, col: usize) -> usize {
+        (row as isize - col as isize + self.n as isize - 1) as usize
+    }
+
+    /// Índice en `diag_plus` de la diagonal "/" que pasa por `(row, col)`.
+    fn diag_plus_idx(&self, row: usize, col: usize) -> usize {
+        row + col
+    }
+
     /// Calcula los tres aspectos que influyen en el costo total de una reina.
     ///
     /// Obtiene el costo de la reina especificada, devuelve un arreglo con
     /// 3 valores correspondientes a el costo por columnas y por las diagonales
-    /// tanto a la derecha como a la izquierda.
+    /// tanto a la derecha como a la izquierda. Se lee directamente de
+    /// `col_count`/`diag_minus`/`diag_plus`, por lo que es O(1).
     ///
     /// of: Número de la reina de la cual calcular el costo
     fn cost_of(&self, of: usize) -> [usize; 3] {
+        let col = self.queens[of];
         [
-            self.column_c(of),
-            self.diagonal_c(of, Side::Left),
-            self.diagonal_c(of, Side::Right),
+            self.col_count[col] - 1,
+            self.diag_minus[self.diag_minus_idx(of, col)] - 1,
+            self.diag_plus[self.diag_plus_idx(of, col)] - 1,
         ]
     }
 
-    /// Calcula el numero de reinas en la misma columna.
-    ///
-    /// Para hacer el cálculo cuenta el número de reinas en el vector
-    /// `self.queens` que tienen el mismo valor que la reina seleccionada.
-    ///
-    /// Esto es posible ya que cada indice en el vector es el número de la reina y la fila donde
-    /// está colocada, y el valor en cada indice es la columna donde está la reina.
+    /// Recalcula `col_count`, `diag_minus` y `diag_plus` desde cero a partir
+    /// de `self.queens`. Se usa cada vez que el tablero se reemplaza por
+    /// completo (estado aleatorio o estado inicial dado por el usuario), y de
+    /// paso reinicia el estado de la búsqueda tabú, que ya no aplica a un
+    /// tablero distinto.
     ///
-    /// Ver la Sección 2.1.1 del reporte para más información.
-    fn column_c(&self, of: usize) -> usize {
-        (0..self.n)
-            .filter(|&x| x != of && self.queens[x] == self.queens[of])
-            .count()
+    /// No toca `best_cost`/`best_board`: `randomize()` también la llama en
+    /// cada reinicio por meseta de `run`, y en ese caso el mejor tablero
+    /// encontrado hasta ahora debe sobrevivir al reinicio, no reemplazarse
+    /// por el costo del nuevo tablero aleatorio. Quien sí quiera arrancar el
+    /// seguimiento del mejor tablero desde cero debe llamar a
+    /// `reset_best_tracking` explícitamente (ver `with_state`,
+    /// `into_random_state`, `into_random_permutation`, `new`).
+    fn rebuild_counters(&mut self) {
+        self.col_count.iter_mut().for_each(|c| *c = 0);
+        self.diag_minus.iter_mut().for_each(|c| *c = 0);
+        self.diag_plus.iter_mut().for_each(|c| *c = 0);
+
+        (0..self.n).for_each(|row| {
+            let col = self.queens[row];
+            self.col_count[col] += 1;
+            let dm = self.diag_minus_idx(row, col);
+            self.diag_minus[dm] += 1;
+            let dp = self.diag_plus_idx(row, col);
+            self.diag_plus[dp] += 1;
+        });
+
+        self.tabu.iter_mut().for_each(|row| row.iter_mut().for_each(|c| *c = 0));
+        self.tabu_iter = 0;
     }
 
-    /// Calcula el numero de reinas en la misma diagonal.
+    /// Reinicia el seguimiento del mejor tablero visto (`best_cost`/
+    /// `best_board`) a partir del estado actual de `self.queens`.
     ///
-    /// Para hacerlo obtenemos la distancia de la fila de cada reina con la
-    /// fila reina seleccionada. Este valor, sumado/restado al valor de la columna
-    /// donde esta posicionada la reina actual, representa el número a buscar en
-    /// el vector `self.queens`.
+    /// Se llama solo cuando el tablero pasa a representar un problema
+    /// distinto (estado inicial nuevo), nunca en un reinicio por meseta
+    /// dentro de una corrida de `run`, para que `best()` siga reflejando el
+    /// mejor costo alcanzado durante toda la búsqueda y no el del último
+    /// reinicio.
+    fn reset_best_tracking(&mut self) {
+        self.best_cost = self.overall_cost();
+        self.best_board = self.queens.clone();
+    }
+
+    /// Mueve la reina de la fila `row` a la columna `new_col`, actualizando
+    /// `col_count`/`diag_minus`/`diag_plus` en O(1) en vez de recalcular el
+    /// tablero completo.
+    fn move_queen(&mut self, row: usize, new_col: usize) {
+        let old_col = self.queens[row];
+        self.col_count[old_col] -= 1;
+        let dm = self.diag_minus_idx(row, old_col);
+        self.diag_minus[dm] -= 1;
+        let dp = self.diag_plus_idx(row, old_col);
+        self.diag_plus[dp] -= 1;
+
+        self.queens[row] = new_col;
+
+        self.col_count[new_col] += 1;
+        let dm = self.diag_minus_idx(row, new_col);
+        self.diag_minus[dm] += 1;
+        let dp = self.diag_plus_idx(row, new_col);
+        self.diag_plus[dp] += 1;
+    }
+
+    /// Contribución al costo total de un grupo (columna o diagonal) con
+    /// `count` reinas: cada par de reinas en el grupo se cuenta dos veces,
+    /// igual que hace `overall_cost` al sumar el costo de cada reina.
+    fn group_cost(count: usize) -> usize {
+        count * count.saturating_sub(1)
+    }
+
+    /// Delta de `group_cost` sumado sobre `counts` al quitar una reina de
+    /// cada índice en `removed` y poner una en cada índice de `added`.
     ///
-    /// Para evitar duplicidad de código unimos la busqueda de ambos lados en diagonal
-    /// en una misma función, el cálculo se hace en base al lado especificado en `side`.
-    fn diagonal_c(&self, of: usize, side: Side) -> usize {
-        (0..self.n)
-            .filter(|&x| {
-                if x != of {
-                    // La distancia de la reina actual a la reina seleccionada
-                    #[allow(clippy::unnecessary_lazy_evaluations)]
-                    let offset = x.checked_sub(of).unwrap_or_else(|| of - x);
-                    // Restamos o sumamos para calcular el valor a buscar en self.queens
-                    if let Some(res) = match side {
-                        Side::Left => self.queens[of].checked_sub(offset),
-                        Side::Right => self.queens[of].checked_add(offset),
-                    } {
-                        // Si es igual al valor calculado devolvemos true,
-                        // lo que incrementa el contador de reinas en la misma diagonal
-                        return self.queens[x] == res;
-                    }
-                }
-                false
+    /// `removed`/`added` pueden compartir índices entre sí (p. ej. dos reinas
+    /// en la misma diagonal antes o después de moverse), así que no basta con
+    /// sumar el delta de cada índice por separado: primero se calcula el
+    /// conteo neto por índice distinto y luego se costea una sola vez. Con
+    /// esto, `step_swap` puede evaluar en O(1) el efecto de mover dos reinas
+    /// a la vez, igual que `cost_after_move` hace para una sola.
+    fn group_delta(counts: &[usize], removed: [usize; 2], added: [usize; 2]) -> isize {
+        let mut idxs = [0usize; 4];
+        let mut len = 0;
+        for &i in removed.iter().chain(added.iter()) {
+            if !idxs[..len].contains(&i) {
+                idxs[len] = i;
+                len += 1;
+            }
+        }
+
+        idxs[..len]
+            .iter()
+            .map(|&i| {
+                let net_removed = removed.iter().filter(|&&x| x == i).count();
+                let net_added = added.iter().filter(|&&x| x == i).count();
+                let old_count = counts[i];
+                let new_count = old_count + net_added - net_removed;
+                Self::group_cost(new_count) as isize - Self::group_cost(old_count) as isize
             })
-            .count()
+            .sum()
+    }
+
+    /// Costo total que resultaría de mover la reina de `row` a `new_col`, sin
+    /// aplicar el movimiento. Se calcula en O(1) ajustando `current_cost` con
+    /// el delta de los tres contadores involucrados (columna y las dos
+    /// diagonales), igual que hace `step` al evaluar columnas candidatas.
+    fn cost_after_move(&self, row: usize, new_col: usize, current_cost: usize) -> usize {
+        let old_col = self.queens[row];
+
+        let c_old_col = self.col_count[old_col];
+        let c_new_col = self.col_count[new_col];
+        let c_dm_old = self.diag_minus[self.diag_minus_idx(row, old_col)];
+        let c_dm_new = self.diag_minus[self.diag_minus_idx(row, new_col)];
+        let c_dp_old = self.diag_plus[self.diag_plus_idx(row, old_col)];
+        let c_dp_new = self.diag_plus[self.diag_plus_idx(row, new_col)];
+
+        let delta = Self::group_cost(c_old_col - 1) as isize - Self::group_cost(c_old_col) as isize
+            + Self::group_cost(c_new_col + 1) as isize - Self::group_cost(c_new_col) as isize
+            + Self::group_cost(c_dm_old - 1) as isize - Self::group_cost(c_dm_old) as isize
+            + Self::group_cost(c_dm_new + 1) as isize - Self::group_cost(c_dm_new) as isize
+            + Self::group_cost(c_dp_old - 1) as isize - Self::group_cost(c_dp_old) as isize
+            + Self::group_cost(c_dp_new + 1) as isize - Self::group_cost(c_dp_new) as isize;
+
+        (current_cost as isize + delta) as usize
+    }
+
+    /// Costo de diagonales que resultaría de intercambiar las columnas de las
+    /// reinas `r1` y `r2`, sin aplicar el intercambio. Se calcula en O(1) vía
+    /// `group_delta`, el mismo patrón de `cost_after_move` pero para las dos
+    /// reinas que se mueven a la vez; como un swap nunca cambia qué columnas
+    /// están ocupadas, `col_count` no participa. Usado por `step_swap` para
+    /// evaluar cada candidato sin recurrir al O(n) de `diagonal_cost`.
+    fn diagonal_cost_after_swap(&self, r1: usize, r2: usize, current_cost: usize) -> usize {
+        let c1 = self.queens[r1];
+        let c2 = self.queens[r2];
+
+        let dm_removed = [self.diag_minus_idx(r1, c1), self.diag_minus_idx(r2, c2)];
+        let dm_added = [self.diag_minus_idx(r1, c2), self.diag_minus_idx(r2, c1)];
+        let dp_removed = [self.diag_plus_idx(r1, c1), self.diag_plus_idx(r2, c2)];
+        let dp_added = [self.diag_plus_idx(r1, c2), self.diag_plus_idx(r2, c1)];
+
+        let delta = Self::group_delta(&self.diag_minus, dm_removed, dm_added)
+            + Self::group_delta(&self.diag_plus, dp_removed, dp_added);
+
+        (current_cost as isize + delta) as usize
+    }
+
+    /// Reemplaza el tablero actual por uno con posiciones aleatorias.
+    fn randomize(&mut self) {
+        self.last_queens.clear();
+        self.permutation_mode = false;
+        let n = self.n;
+        for row in 0..n {
+            let col = self.rng.gen_range(0..n);
+            self.queens[row] = col;
+        }
+        self.rebuild_counters();
     }
 
     /// Genera un estado aleatorio incial.
@@ -88,10 +539,27 @@ impl NQueens {
     /// con todos los valores en 0 y un estado aleatorio de posiciones
     /// de reinas.
     pub fn into_random_state(mut self) -> Self {
+        self.randomize();
+        self.reset_best_tracking();
+        self
+    }
+
+    /// Genera un estado aleatorio inicial restringido a permutaciones de
+    /// `0..n` (baraja de Fisher-Yates), de modo que cada columna se usa
+    /// exactamente una vez y los conflictos de columna son cero por
+    /// construcción; solo quedan por resolver los conflictos de diagonal.
+    /// Pensado para usarse junto con `step_swap`.
+    pub fn into_random_permutation(mut self) -> Self {
         self.last_queens.clear();
-        self.queens.iter_mut().for_each(|queen| {
-            *queen = rand::random::<usize>() % self.n;
-        });
+        let n = self.n;
+        self.queens = (0..n).collect();
+        for i in (1..n).rev() {
+            let j = self.rng.gen_range(0..=i);
+            self.queens.swap(i, j);
+        }
+        self.permutation_mode = true;
+        self.rebuild_counters();
+        self.reset_best_tracking();
         self
     }
 
@@ -101,14 +569,94 @@ impl NQueens {
         self
     }
 
+    /// Asigna la estrategia de desempate usada en `step`.
+    pub fn with_tie_strategy(mut self, value: TieStrategy) -> Self {
+        self.tie_strategy = value;
+        self
+    }
+
+    /// Fija la semilla usada para todas las decisiones aleatorias del problema.
+    ///
+    /// Con la misma semilla, el mismo N y el mismo estado inicial, `step`
+    /// produce siempre la misma secuencia de movimientos, lo que permite
+    /// reproducir corridas y medir benchmarks de forma estable.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Asigna cuántas iteraciones sin mejora estricta del costo se toleran
+    /// antes de que `run` fuerce un reinicio aleatorio completo.
+    pub fn with_plateau_limit(mut self, value: usize) -> Self {
+        self.plateau_limit = value;
+        self
+    }
+
+    /// Asigna cuántas iteraciones permanece prohibido revertir un movimiento
+    /// en `step_tabu`.
+    pub fn with_tabu_tenure(mut self, value: usize) -> Self {
+        self.tabu_tenure = value;
+        self
+    }
+
+    /// El mejor tablero visto hasta ahora, y su costo.
+    ///
+    /// Se actualiza en cada mejora estricta de `step_tabu`/`anneal`/`run`, y
+    /// sobrevive a los reinicios por meseta de `run` (ver
+    /// `reset_best_tracking`), así que refleja el mejor tablero de toda la
+    /// corrida hecha con esos métodos. `step` y `step_swap`, llamados por sí
+    /// solos, no lo actualizan. Solo se
+    /// reinicia al estado actual cuando se carga un tablero nuevo por
+    /// completo (`with_state`, `into_random_state`, `into_random_permutation`,
+    /// `new`), ya que en ese punto se trata de un problema distinto.
+    pub fn best(&self) -> (&[usize], usize) {
+        (&self.best_board, self.best_cost)
+    }
+
+    /// Escoge uno de los `candidatos` empatados de acuerdo a `self.tie_strategy`.
+    ///
+    /// Cada candidato es una tupla `(indice, costo, valor_previo)` tal como las
+    /// que se almacenan en `self.costs`; el desempate por `First`/`Last` opera
+    /// sobre el campo `indice`.
+    fn resolve_tie(
+        &self,
+        candidates: &[(usize, usize, usize)],
+        rng: &mut impl Rng,
+    ) -> (usize, usize, usize) {
+        match self.tie_strategy {
+            TieStrategy::First => *candidates.iter().min_by_key(|c| c.0).unwrap(),
+            TieStrategy::Last => *candidates.iter().max_by_key(|c| c.0).unwrap(),
+            TieStrategy::Random => candidates.iter().choose(rng).copied().unwrap(),
+            TieStrategy::Prompt => {
+                println!("Candidatos empatados: {:?}", candidates);
+                let mut buff = String::new();
+                let elegido = pedir_valor("Elige el índice a usar: ", &mut buff, |inp| {
+                    inp.parse::<usize>()
+                        .map_err(|_| "Valor inválido, ingresa un índice de la lista.".into())
+                })
+                .ok()
+                .and_then(|idx: usize| candidates.iter().find(|c| c.0 == idx).copied());
+
+                elegido.unwrap_or_else(|| {
+                    println!("Índice no válido, se tomará el primero de la lista.");
+                    candidates[0]
+                })
+            }
+        }
+    }
+
     /// Asigna un estado inicial.
     pub fn with_state(mut self, state: &[usize]) -> Option<Self> {
         (state.len() == self.queens.capacity()).then(|| {
             self.last_queens.clear();
+            self.permutation_mode = false;
             self.queens
                 .iter_mut()
                 .zip(state.iter())
                 .for_each(|(q, &s)| *q = s);
+            self.rebuild_counters();
+            self.reset_best_tracking();
             self
         })
     }
@@ -126,7 +674,16 @@ impl NQueens {
     /// Calcula el siguiente estado del tablero
     /// Devuelve el costo del nuevo estado
     pub fn step(&mut self) -> usize {
-        let mut rng = rand::thread_rng();
+        // `step` puede reasignar una reina a una columna ya ocupada, así que
+        // deja de cumplirse la garantía de `into_random_permutation`; si no
+        // se apaga aquí, `Display` seguiría ocultando `cc` y subreportando
+        // el costo total (`tt`) de un tablero que ya no es una permutación.
+        self.permutation_mode = false;
+
+        // Clonamos el generador para poder usarlo libremente durante el paso
+        // sin pelear con el borrow checker, y lo guardamos de vuelta al final
+        // para que el siguiente `step` continúe la misma secuencia.
+        let mut rng = self.rng.clone();
 
         // Obtenemos el costo de cada una de las reinas en el estado actual
         (0..self.n).for_each(|queen| {
@@ -142,30 +699,29 @@ impl NQueens {
         // Obtenemos la reina más cara
         let worst_value = self.costs.last().map(|&x| x.1).unwrap();
 
-        // Escogemos una reina aleatoria de entre las que son igual de caras
-        // que la reina más cara
-        let (worst_pos, _, prev_val) = self
+        // Escogemos, de acuerdo a `self.tie_strategy`, una reina de entre las
+        // que son igual de caras que la reina más cara
+        let tied_worst: Vec<_> = self
             .costs
             .iter()
             .filter(|&x| x.1 == worst_value)
-            .choose(&mut rng)
             .copied()
-            .unwrap();
+            .collect();
+        let (worst_pos, _, prev_val) = self.resolve_tie(&tied_worst, &mut rng);
 
         // Ahora vamos a cambiar la posición de la reina que más costo tiene
         // para reducir su costo.
         //
-        // Comenzamos probando y calculando el costo de mover la reina a todas las posiciones
-        // del 0 a N
+        // Comenzamos calculando el costo resultante de mover la reina a todas
+        // las columnas del 0 a N. Como la reina no está actualmente en `col`,
+        // el costo resultante se lee directamente de los contadores en O(1),
+        // sin tener que mover la reina y deshacer el movimiento para medirlo.
         (0..self.n).filter(|&col| col != prev_val).for_each(|col| {
+            let dm = self.diag_minus_idx(worst_pos, col);
+            let dp = self.diag_plus_idx(worst_pos, col);
             self.costs[col] = (
                 col,
-                {
-                    self.queens[worst_pos] = col;
-                    let res = self.cost_of(worst_pos).iter().sum::<usize>();
-                    self.queens[worst_pos] = prev_val;
-                    res
-                },
+                self.col_count[col] + self.diag_minus[dm] + self.diag_plus[dp],
                 0,
             )
         });
@@ -175,48 +731,535 @@ impl NQueens {
 
         // Obtenemos el valor de la nueva posible posicion para la reina.
         let (_, best_cost, _) = self.costs[0];
-        // Escogemos aleatoriamente entre cualquiera de los posibles valores
-        // que reducen el costo de la peor reina de igual manera que el mejor valor.
-        let (new_cost, _, _) = self
+        // Escogemos, de acuerdo a `self.tie_strategy`, entre cualquiera de los
+        // posibles valores que reducen el costo de la peor reina de igual
+        // manera que el mejor valor.
+        let tied_best: Vec<_> = self
             .costs
             .iter()
             .filter(|&x| x.1 == best_cost)
-            .choose(&mut rng)
             .copied()
-            .unwrap();
+            .collect();
+        let (new_cost, _, _) = self.resolve_tie(&tied_best, &mut rng);
 
         // Verificamos si ya hemos visto el gen actual en el pasado.
         // Si encontramos colisiones, entonces se trata de un camino sin salida
         if self.last_queens.contains(&self.queens) {
             // Forzamos algo de aleatoriedad
-            self.queens[rng.gen_range(0..self.n)] = rng.gen_range(0..self.n);
+            let row = rng.gen_range(0..self.n);
+            let col = rng.gen_range(0..self.n);
+            self.move_queen(row, col);
         } else {
             self.last_queens.insert(self.queens.clone());
         }
 
         // Asignamos a la reina con mayor costo uno de los valores
         // que reducen más el costo
-        self.queens[worst_pos] = new_cost;
+        self.move_queen(worst_pos, new_cost);
+
+        self.rng = rng;
 
         // Devolvemos el costo del tablero entero
         self.overall_cost()
     }
 
+    /// Calcula el siguiente estado del tablero usando búsqueda tabú.
+    ///
+    /// A diferencia de `step`, que solo evita ciclos reaccionando cuando ya
+    /// vio el estado actual, aquí se evalúa el vecindario completo (cada
+    /// reina en conflicto, movida a cada otra columna) y se escoge el
+    /// movimiento que más reduce `overall_cost` entre los que no son tabú.
+    /// Un movimiento tabú todavía se permite si su resultado mejora el mejor
+    /// costo visto hasta ahora (criterio de aspiración). El movimiento
+    /// contrario al elegido queda prohibido por `self.tabu_tenure`
+    /// iteraciones, para no deshacer justo lo que acabamos de mejorar.
+    ///
+    /// Devuelve el costo del nuevo estado.
+    pub fn step_tabu(&mut self) -> usize {
+        // Igual que `step`: este método puede introducir conflictos de
+        // columna, así que el tablero deja de ser una permutación.
+        self.permutation_mode = false;
+
+        self.tabu_iter += 1;
+        let current_iter = self.tabu_iter;
+        let current_cost = self.overall_cost();
+
+        // (fila, columna, costo resultante) del mejor movimiento encontrado
+        let mut best_move: Option<(usize, usize, usize)> = None;
+
+        for row in 0..self.n {
+            if self.cost_of(row).into_iter().sum::<usize>() == 0 {
+                // Esta reina no está en conflicto, no tiene sentido moverla.
+                continue;
+            }
+
+            let old_col = self.queens[row];
+            for col in 0..self.n {
+                if col == old_col {
+                    continue;
+                }
+
+                let resulting_cost = self.cost_after_move(row, col, current_cost);
+                let is_tabu = self.tabu[row][col] >= current_iter;
+                let aspiration = resulting_cost < self.best_cost;
+                if is_tabu && !aspiration {
+                    continue;
+                }
+
+                let is_better =
+                    best_move.is_none_or(|(_, _, best_cost)| resulting_cost < best_cost);
+                if is_better {
+                    best_move = Some((row, col, resulting_cost));
+                }
+            }
+        }
+
+        if let Some((row, col, _)) = best_move {
+            let old_col = self.queens[row];
+            self.move_queen(row, col);
+            // Prohibimos deshacer este movimiento por un rato.
+            self.tabu[row][old_col] = current_iter + self.tabu_tenure;
+        }
+
+        let new_cost = self.overall_cost();
+        if new_cost < self.best_cost {
+            self.best_cost = new_cost;
+            self.best_board = self.queens.clone();
+        }
+        new_cost
+    }
+
+    /// Corre temple simulado (simulated annealing) según `config`.
+    ///
+    /// A diferencia de `step`/`step_tabu`, que siempre toman el mejor
+    /// movimiento disponible, aquí cada iteración escoge una reina y una
+    /// columna destino al azar y acepta el movimiento si reduce el costo o,
+    /// si lo empeora en `delta`, con probabilidad `exp(-delta/t)`. La
+    /// temperatura `t` arranca en `config.t0` y se enfría geométricamente por
+    /// `config.alpha` en cada iteración, hasta agotar `config.budget`.
+    ///
+    /// Devuelve el menor costo encontrado durante la corrida, recordado en
+    /// `self.best_board`/`self.best_cost` igual que hace `step_tabu`.
+    pub fn anneal(&mut self, config: &SimulatedAnnealing) -> usize {
+        // Igual que `step`/`step_tabu`: los movimientos aceptados aquí
+        // pueden repetir columna, así que el tablero deja de ser una
+        // permutación.
+        self.permutation_mode = false;
+
+        // Mismo patrón de clonar-y-reasignar el rng que usa `step`, para no
+        // pelear con el borrow checker mientras se toman prestados otros campos.
+        let mut rng = self.rng.clone();
+        let mut t = config.t0;
+        let start = Instant::now();
+        let mut iterations = 0usize;
+        let mut current_cost = self.overall_cost();
+
+        loop {
+            let done = match config.budget {
+                AnnealingBudget::Iterations(max) => iterations >= max,
+                AnnealingBudget::Time(dur) => start.elapsed() >= dur,
+            };
+            if done || current_cost == 0 {
+                break;
+            }
+
+            let row = rng.gen_range(0..self.n);
+            let new_col = rng.gen_range(0..self.n);
+            if new_col != self.queens[row] {
+                let candidate_cost = self.cost_after_move(row, new_col, current_cost);
+                let delta = candidate_cost as isize - current_cost as isize;
+                let accept = delta <= 0 || rng.gen::<f64>() < (-(delta as f64) / t).exp();
+
+                if accept {
+                    self.move_queen(row, new_col);
+                    current_cost = candidate_cost;
+
+                    if current_cost < self.best_cost {
+                        self.best_cost = current_cost;
+                        self.best_board = self.queens.clone();
+                    }
+                }
+            }
+
+            t *= config.alpha;
+            iterations += 1;
+        }
+
+        self.rng = rng;
+        self.best_cost
+    }
+
+    /// Costo de solo las dos familias de diagonales, ignorando columnas.
+    ///
+    /// En modo permutación (`self.permutation_mode`) los conflictos de
+    /// columna son siempre cero por construcción, así que este es el único
+    /// costo que `step_swap` necesita evaluar.
+    fn diagonal_cost(&self) -> usize {
+        (0..self.n)
+            .map(|row| {
+                let col = self.queens[row];
+                (self.diag_minus[self.diag_minus_idx(row, col)] - 1)
+                    + (self.diag_plus[self.diag_plus_idx(row, col)] - 1)
+            })
+            .sum()
+    }
+
+    /// Calcula el siguiente estado del tablero en modo permutación,
+    /// intercambiando las columnas de dos reinas en vez de reasignar una sola.
+    ///
+    /// Como la columna de cada reina es única por construcción
+    /// (`into_random_permutation`), un swap nunca introduce conflictos de
+    /// columna nuevos, así que solo se evalúa el efecto sobre
+    /// `diagonal_cost`. Se prueba cada par de reinas, se escoge el swap que
+    /// más reduce el costo de diagonales (empates resueltos siempre al azar,
+    /// a propósito sin pasar por `self.tie_strategy`: este método no respeta
+    /// la estrategia de desempate global). Si ningún swap mejora, el tablero
+    /// se guarda en `last_queens` sin cambios; si ya estaba ahí, se aplica un
+    /// swap al azar para escapar del estancamiento, igual que hace `step`.
+    /// Cada candidato se evalúa en O(1) vía `diagonal_cost_after_swap` (el
+    /// mismo patrón de delta que `cost_after_move`), en vez de aplicar el
+    /// swap de verdad y recalcular `diagonal_cost` entero para deshacerlo
+    /// después, lo que dejaba esta función en O(n³).
+    ///
+    /// Devuelve el nuevo costo de diagonales.
+    pub fn step_swap(&mut self) -> usize {
+        let mut rng = self.rng.clone();
+        let current_cost = self.diagonal_cost();
+
+        // Candidatos (idx, costo, r2) con idx = r1 * n + r2.
+        let mut best_cost = current_cost;
+        let mut candidates: Vec<(usize, usize, usize)> = Vec::new();
+
+        for r1 in 0..self.n {
+            for r2 in (r1 + 1)..self.n {
+                let cost = self.diagonal_cost_after_swap(r1, r2, current_cost);
+
+                if cost < best_cost {
+                    best_cost = cost;
+                    candidates.clear();
+                    candidates.push((r1 * self.n + r2, cost, r2));
+                } else if cost == best_cost && cost < current_cost {
+                    candidates.push((r1 * self.n + r2, cost, r2));
+                }
+            }
+        }
+
+        if candidates.is_empty() {
+            // Ningún swap mejora el costo actual: si este tablero ya se había
+            // visto, forzamos uno al azar para no quedarnos estancados, igual
+            // que el nudge de `step`.
+            if self.last_queens.contains(&self.queens) {
+                let r1 = rng.gen_range(0..self.n);
+                let r2 = rng.gen_range(0..self.n);
+                if r1 != r2 {
+                    let c1 = self.queens[r1];
+                    let c2 = self.queens[r2];
+                    self.move_queen(r1, c2);
+                    self.move_queen(r2, c1);
+                }
+            } else {
+                self.last_queens.insert(self.queens.clone());
+            }
+        } else {
+            // A diferencia de `step`, que desempata según `self.tie_strategy`,
+            // el pedido original para `step_swap` es explícito: los empates
+            // se rompen al azar siempre, sin importar la estrategia de
+            // desempate configurada por el usuario para el resto del solver.
+            let (idx, _, r2) = *candidates.iter().choose(&mut rng).unwrap();
+            let r1 = idx / self.n;
+            let c1 = self.queens[r1];
+            let c2 = self.queens[r2];
+            self.move_queen(r1, c2);
+            self.move_queen(r2, c1);
+        }
+
+        self.rng = rng;
+        self.diagonal_cost()
+    }
+
+    /// Backtracking recursivo usado por `solve_all`/`count_solutions`: intenta
+    /// colocar una reina en cada columna libre de la fila `row`, marcando y
+    /// desmarcando las tres familias de ocupación (columna y ambas
+    /// diagonales) al entrar y salir de cada rama a través de `occ`, tres
+    /// arreglos booleanos análogos a los contadores incrementales de la
+    /// búsqueda local, pero de ocupación (0 o 1 reina) en vez de conteo, ya
+    /// que en una solución exacta ninguna reina se ataca. Se detiene antes de
+    /// tiempo si `solutions.len()` alcanza `cap`.
+    fn backtrack(
+        row: usize,
+        placement: &mut Vec<usize>,
+        occ: &mut Occupancy,
+        cap: Option<usize>,
+        solutions: &mut Vec<Vec<usize>>,
+    ) {
+        if let Some(cap) = cap {
+            if solutions.len() >= cap {
+                return;
+            }
+        }
+
+        if row == occ.n {
+            solutions.push(placement.clone());
+            return;
+        }
+
+        for col in 0..occ.n {
+            if occ.is_occupied(row, col) {
+                continue;
+            }
+
+            occ.occupy(row, col);
+            placement.push(col);
+
+            Self::backtrack(row + 1, placement, occ, cap, solutions);
+
+            placement.pop();
+            occ.free(row, col);
+
+            if let Some(cap) = cap {
+                if solutions.len() >= cap {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Enumera todas las soluciones exactas (costo 0) de un tablero de `n`
+    /// reinas mediante backtracking de permutaciones, en vez de la búsqueda
+    /// local estocástica del resto del archivo.
+    ///
+    /// Coloca una reina por fila en orden creciente, probando cada columna
+    /// libre y comprobando en O(1) si la columna o alguna de las dos
+    /// diagonales ya está ocupada. Si `cap` es `Some(k)`, se detiene tras
+    /// encontrar `k` soluciones (útil para obtener rápidamente una sola
+    /// solución constructiva que alimentar a `with_state`); con `None`
+    /// encuentra todas.
+    ///
+    /// `n == 0` es un caso límite válido: hay exactamente una forma de
+    /// colocar cero reinas en un tablero vacío (la solución vacía), así que
+    /// se devuelve directamente sin construir los arreglos de ocupación, que
+    /// de otro modo underflowearían al calcular `2 * n - 1`.
+    pub fn solve_all(n: usize, cap: Option<usize>) -> Vec<Vec<usize>> {
+        if n == 0 {
+            return match cap {
+                Some(0) => vec![],
+                _ => vec![vec![]],
+            };
+        }
+
+        let mut solutions = Vec::new();
+        let mut placement = Vec::with_capacity(n);
+        let mut occ = Occupancy::new(n);
+
+        Self::backtrack(0, &mut placement, &mut occ, cap, &mut solutions);
+
+        solutions
+    }
+
+    /// Cuenta las soluciones exactas de un tablero de `n` reinas. Reutiliza
+    /// `solve_all`, así que las almacena todas antes de devolver `len()`.
+    ///
+    /// Sirve para validar el solver contra la secuencia conocida OEIS A000170.
+    pub fn count_solutions(n: usize) -> usize {
+        Self::solve_all(n, None).len()
+    }
+
+    /// Costo de un tablero arbitrario dado como `Vec<usize>` (columna por
+    /// fila), sin depender de los contadores incrementales de ninguna
+    /// instancia en particular. Existe para que `LocalSearchProblem::cost`
+    /// pueda costear cualquier `state`, no solo `self.queens`.
+    ///
+    /// Construye los tres conteos por grupo (columna y ambas diagonales) en
+    /// O(n), igual que `rebuild_counters`, y de ahí suma `group_cost` por
+    /// grupo en vez de recorrer todos los pares de reinas, lo que deja este
+    /// cálculo en O(n) en lugar de O(n²). Cada par de reinas en conflicto se
+    /// cuenta dos veces, igual que `overall_cost`, para que ambos costos sean
+    /// comparables.
+    fn board_cost(board: &[usize]) -> usize {
+        let n = board.len();
+        if n == 0 {
+            return 0;
+        }
+
+        let mut col_count = vec![0usize; n];
+        let mut diag_minus = vec![0usize; 2 * n - 1];
+        let mut diag_plus = vec![0usize; 2 * n - 1];
+
+        for (row, &col) in board.iter().enumerate() {
+            col_count[col] += 1;
+            diag_minus[row + n - 1 - col] += 1;
+            diag_plus[row + col] += 1;
+        }
+
+        col_count.iter().map(|&c| Self::group_cost(c)).sum::<usize>()
+            + diag_minus.iter().map(|&c| Self::group_cost(c)).sum::<usize>()
+            + diag_plus.iter().map(|&c| Self::group_cost(c)).sum::<usize>()
+    }
+
+    /// Corre `step` repetidamente hasta encontrar una solución o agotar
+    /// `max_iterations`/`max_restarts`.
+    ///
+    /// A diferencia de un `while nqueens.step() != 0 { }` manual, esta capa
+    /// lleva la cuenta de cuántas iteraciones han pasado desde la última
+    /// mejora estricta del costo; al superar `self.plateau_limit` fuerza un
+    /// reinicio aleatorio completo (en vez de depender únicamente de la
+    /// perturbación de una sola celda que `step` aplica al detectar un ciclo).
+    /// Así el llamador sabe si realmente se encontró una solución y cuánto
+    /// trabajo costó.
+    ///
+    /// Lleva el mejor costo en `self.best_cost`/`self.best_board` (no en una
+    /// variable local), igual que `step_tabu`/`anneal`: un reinicio por
+    /// meseta llama a `self.randomize()` directamente (no `into_random_state`),
+    /// que no toca esos campos, así que el mejor tablero encontrado antes del
+    /// reinicio sigue siendo el que reporta `best()`/`GaveUp` al terminar.
+    pub fn run(&mut self, max_iterations: usize, max_restarts: usize) -> SolveOutcome {
+        let mut iterations = 0;
+        let mut restarts = 0;
+        let mut since_improvement = 0;
+
+        while iterations < max_iterations {
+            let cost = self.step();
+            iterations += 1;
+            if self.verbose {
+                println!("{self}\n");
+            }
+
+            if cost < self.best_cost {
+                self.best_cost = cost;
+                self.best_board = self.queens.clone();
+                since_improvement = 0;
+            } else {
+                since_improvement += 1;
+            }
+
+            if cost == 0 {
+                return SolveOutcome::Solved {
+                    iterations,
+                    restarts,
+                };
+            }
+
+            if since_improvement >= self.plateau_limit {
+                if restarts >= max_restarts {
+                    break;
+                }
+                self.randomize();
+                restarts += 1;
+                since_improvement = 0;
+            }
+        }
+
+        SolveOutcome::GaveUp {
+            best_cost: self.best_cost,
+            iterations,
+            restarts,
+        }
+    }
+
+    /// Corre `run` y empaqueta el resultado junto con el tablero final en un
+    /// [`SolveReport`], para poder comparar este modo de búsqueda (greedy con
+    /// reinicios) contra `step_tabu`/`anneal` bajo la misma interfaz.
+    ///
+    /// En el caso `GaveUp` el tablero reportado es `self.best_board`, no
+    /// `self.queens`: tras un reinicio por meseta, `self.queens` es el último
+    /// tablero aleatorio visitado, que puede ser peor que el mejor encontrado
+    /// durante la corrida (el mismo que reporta `best_cost`).
+    pub fn solve(&mut self, max_iterations: usize, max_restarts: usize) -> SolveReport {
+        match self.run(max_iterations, max_restarts) {
+            SolveOutcome::Solved {
+                iterations,
+                restarts,
+            } => SolveReport {
+                solved: true,
+                board: self.queens.clone(),
+                iterations,
+                restarts,
+                best_cost: 0,
+            },
+            SolveOutcome::GaveUp {
+                best_cost,
+                iterations,
+                restarts,
+            } => SolveReport {
+                solved: false,
+                board: self.best_board.clone(),
+                iterations,
+                restarts,
+                best_cost,
+            },
+        }
+    }
+
     /// Generamos un nuevo tablero de NxN para colocar N reinas.
     ///
     /// El código no esta pensado para tableros de tamaño menor a 4x4, por lo que
     /// si el tamaño deseado de tablero en `with_n` es menor a 4 no creamos la instancia.
     pub fn new(with_n: usize) -> Option<Self> {
-        (with_n >= 4).then_some(NQueens {
-            n: with_n,
-            queens: vec![0; with_n],
-            last_queens: HashSet::with_capacity(with_n * with_n),
-            costs: vec![(0, 0, 0); with_n],
-            verbose: false,
+        (with_n >= 4).then_some(()).map(|_| {
+            let mut nqueens = NQueens {
+                n: with_n,
+                queens: vec![0; with_n],
+                col_count: vec![0; with_n],
+                diag_minus: vec![0; 2 * with_n - 1],
+                diag_plus: vec![0; 2 * with_n - 1],
+                last_queens: HashSet::with_capacity(with_n * with_n),
+                costs: vec![(0, 0, 0); with_n],
+                verbose: false,
+                tie_strategy: TieStrategy::Random,
+                seed: None,
+                rng: StdRng::from_entropy(),
+                plateau_limit: 50,
+                tabu: vec![vec![0; with_n]; with_n],
+                tabu_tenure: 10,
+                tabu_iter: 0,
+                best_board: vec![0; with_n],
+                best_cost: 0,
+                permutation_mode: false,
+            };
+            nqueens.rebuild_counters();
+            nqueens.reset_best_tracking();
+            nqueens
         })
     }
 }
 
+/// `NQueens` como implementador de [`LocalSearchProblem`], para poder
+/// resolverlo con un [`LocalSearchEngine`] genérico además de con sus
+/// métodos especializados (`step`, `step_tabu`, `anneal`), que siguen siendo
+/// la vía rápida: estos usan los contadores incrementales de `self` en O(1)
+/// por vecino, mientras que `cost` aquí recalcula `state` desde cero en O(n)
+/// vía `board_cost`, porque el estado que llega no es necesariamente
+/// `self.queens`. `neighbors` sigue generando los O(n²) tableros candidatos
+/// completos (inevitable: el motor genérico no conoce contadores de `self`),
+/// así que `best_neighbor` queda en O(n³) para N-Queens en vez del O(n) de
+/// `step`; quien necesite el caso rápido debe usar `step` directamente.
+impl LocalSearchProblem for NQueens {
+    type State = Vec<usize>;
+
+    fn neighbors(&self, state: &Self::State) -> Vec<Self::State> {
+        let n = state.len();
+        let mut neighbors = Vec::with_capacity(n * n.saturating_sub(1));
+        for row in 0..n {
+            for col in 0..n {
+                if col != state[row] {
+                    let mut neighbor = state.clone();
+                    neighbor[row] = col;
+                    neighbors.push(neighbor);
+                }
+            }
+        }
+        neighbors
+    }
+
+    fn cost(&self, state: &Self::State) -> usize {
+        Self::board_cost(state)
+    }
+
+    fn random_state(&self, rng: &mut StdRng) -> Self::State {
+        (0..self.n).map(|_| rng.gen_range(0..self.n)).collect()
+    }
+}
+
 /// Implementación de la caracteristica Display
 ///
 /// Esta característica se encarga de dictar cómo debe imprimirse
@@ -234,11 +1277,17 @@ impl std::fmt::Display for NQueens {
             if self.verbose {
                 let cost = self.cost_of(row);
                 let [cval, lval, rval] = { [cost[0], cost[1], cost[2]] };
-                write!(
-                    f,
-                    " | ld:{lval:>2} rd:{rval:>2} cc:{cval:>2} | tt:{:>2}",
-                    cost.iter().sum::<usize>()
-                )?;
+                if self.permutation_mode {
+                    // En modo permutación `cval` siempre es 0 por construcción
+                    // (ver `into_random_permutation`), así que no vale la pena mostrarlo.
+                    write!(f, " | ld:{lval:>2} rd:{rval:>2} | tt:{:>2}", lval + rval)?;
+                } else {
+                    write!(
+                        f,
+                        " | ld:{lval:>2} rd:{rval:>2} cc:{cval:>2} | tt:{:>2}",
+                        cost.iter().sum::<usize>()
+                    )?;
+                }
             }
             if row != self.n - 1 {
                 writeln!(f)?;
@@ -248,3 +1297,474 @@ impl std::fmt::Display for NQueens {
         Ok(())
     }
 }
+
+/// Opciones de una corrida del solver, sin atarlas a los prompts interactivos.
+///
+/// Se puede obtener de dos formas: [`SolverOptions::from_args`] las parsea de
+/// `std::env::args`, lo que permite correr el solver desde un script sin
+/// interacción; [`SolverOptions::prompt`] hace las mismas preguntas que antes
+/// se hacían por separado en cada `main`. Ambos `main` del crate comparten
+/// este tipo en vez de duplicar el parseo de stdin.
+#[derive(Clone, Debug)]
+pub struct SolverOptions {
+    pub n: usize,
+    pub verbose: bool,
+    pub seed: Option<u64>,
+    pub initial_state: Option<Vec<usize>>,
+    pub tie_strategy: TieStrategy,
+    pub max_iterations: usize,
+    pub max_restarts: usize,
+    pub plateau_limit: usize,
+}
+
+impl SolverOptions {
+    /// Parsea las opciones desde los argumentos de línea de comandos.
+    ///
+    /// Devuelve `None` si el programa se invocó sin argumentos, para que el
+    /// llamador pueda recurrir a [`SolverOptions::prompt`].
+    pub fn from_args() -> Option<Result<Self, Cow<'static, str>>> {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        (!args.is_empty()).then(|| Self::parse_args(&args))
+    }
+
+    fn parse_args(args: &[String]) -> Result<Self, Cow<'static, str>> {
+        let mut n = None;
+        let mut verbose = false;
+        let mut seed = None;
+        let mut initial_state = None;
+        let mut tie_strategy = TieStrategy::Random;
+        let mut max_iterations = 10_000;
+        let mut max_restarts = 10;
+        let mut plateau_limit = 50;
+
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            match flag.as_str() {
+                "--n" => {
+                    let val = args.next().ok_or("--n requiere un valor")?;
+                    n = Some(
+                        val.parse::<usize>()
+                            .map_err(|_| format!("Valor de --n inválido: '{val}'"))?,
+                    );
+                }
+                "--verbose" => verbose = true,
+                "--seed" => {
+                    let val = args.next().ok_or("--seed requiere un valor")?;
+                    seed = Some(
+                        val.parse::<u64>()
+                            .map_err(|_| format!("Valor de --seed inválido: '{val}'"))?,
+                    );
+                }
+                "--state" => {
+                    let val = args.next().ok_or("--state requiere un valor")?;
+                    initial_state = Some(
+                        val.trim_start_matches('[')
+                            .trim_end_matches(']')
+                            .split(',')
+                            .map(|v| v.trim().parse::<usize>())
+                            .collect::<Result<Vec<_>, _>>()
+                            .map_err(|_| format!("Valor de --state inválido: '{val}'"))?,
+                    );
+                }
+                "--ties" => {
+                    let val = args.next().ok_or("--ties requiere un valor")?;
+                    tie_strategy = match val.to_lowercase().as_str() {
+                        "first" => TieStrategy::First,
+                        "last" => TieStrategy::Last,
+                        "random" => TieStrategy::Random,
+                        "prompt" => TieStrategy::Prompt,
+                        _ => return Err(format!("Valor de --ties inválido: '{val}'").into()),
+                    };
+                }
+                "--max-iters" => {
+                    let val = args.next().ok_or("--max-iters requiere un valor")?;
+                    max_iterations = val
+                        .parse::<usize>()
+                        .map_err(|_| format!("Valor de --max-iters inválido: '{val}'"))?;
+                }
+                "--max-restarts" => {
+                    let val = args.next().ok_or("--max-restarts requiere un valor")?;
+                    max_restarts = val
+                        .parse::<usize>()
+                        .map_err(|_| format!("Valor de --max-restarts inválido: '{val}'"))?;
+                }
+                "--plateau" => {
+                    let val = args.next().ok_or("--plateau requiere un valor")?;
+                    plateau_limit = val
+                        .parse::<usize>()
+                        .map_err(|_| format!("Valor de --plateau inválido: '{val}'"))?;
+                }
+                other => return Err(format!("Argumento desconocido: '{other}'").into()),
+            }
+        }
+
+        let n = n.ok_or("Falta el argumento requerido --n")?;
+        (n >= 4).then_some(()).ok_or("No se permiten valores de N menores a 4")?;
+
+        Ok(SolverOptions {
+            n,
+            verbose,
+            seed,
+            initial_state,
+            tie_strategy,
+            max_iterations,
+            max_restarts,
+            plateau_limit,
+        })
+    }
+
+    /// Hace las mismas preguntas interactivas que antes vivían duplicadas en
+    /// cada `main`, y arma las opciones a partir de las respuestas.
+    pub fn prompt() -> Result<Self, Cow<'static, str>> {
+        let mut buff = String::new();
+
+        let n = pedir_valor("Ingresa el valor de N: ", &mut buff, |inp| {
+            let val = inp
+                .parse::<usize>()
+                .map_err(|_| "Valor de N inválido. Ingresa un valor de N válido.")?;
+
+            (val >= 4)
+                .then_some(val)
+                .ok_or("No se permiten valores de N menores a 4".into())
+        })?;
+
+        let verbose = pedir_valor(
+            "Deseas mostrar información para cada paso? [y/N]: ",
+            &mut buff,
+            |inp| {
+                Ok((inp == "y" || inp == "Y")
+                    .then_some(true)
+                    .unwrap_or_else(|| {
+                        println!("Valor inválido, se considerará como que no desea información.");
+                        false
+                    }))
+            },
+        )?;
+
+        let tie_strategy = pedir_valor(
+            "Estrategia de desempate [first/last/random/prompt] (default random): ",
+            &mut buff,
+            |inp| match inp.to_lowercase().as_str() {
+                "" | "random" => Ok(TieStrategy::Random),
+                "first" => Ok(TieStrategy::First),
+                "last" => Ok(TieStrategy::Last),
+                "prompt" => Ok(TieStrategy::Prompt),
+                _ => Err("Estrategia inválida, usa first, last, random o prompt.".into()),
+            },
+        )?;
+
+        let wants_seed = pedir_valor(
+            "Deseas fijar una semilla para reproducir la corrida? [y/N]: ",
+            &mut buff,
+            |inp| {
+                Ok((inp == "y" || inp == "Y")
+                    .then_some(true)
+                    .unwrap_or_else(|| {
+                        println!(
+                            "Valor inválido, se considerará como que no desea fijar una semilla."
+                        );
+                        false
+                    }))
+            },
+        )?;
+
+        let seed = wants_seed
+            .then(|| {
+                pedir_valor("Ingresa la semilla (u64): ", &mut buff, |inp| {
+                    inp.parse::<u64>()
+                        .map_err(|_| "Semilla inválida. Ingresa un entero sin signo.".into())
+                })
+            })
+            .transpose()?;
+
+        let wants_init = pedir_valor(
+            "Deseas ingresar un estado inicial para el problema? [y/N]: ",
+            &mut buff,
+            |inp| {
+                Ok((inp == "y" || inp == "Y")
+                    .then_some(true)
+                    .unwrap_or_else(|| {
+                        println!(
+                            "Valor inválido, se considerará como que no desea un estado inicial."
+                        );
+                        false
+                    }))
+            },
+        )?;
+
+        let initial_state = wants_init.then(|| {
+            println!(
+                r#"
+    Ingresa los valores del estado separados por comas
+    Un ejemplo de estado es [0, 3, 2, 1] para una N = 4
+        En el ejemplo:
+            - La reina 0 esta en la fila 0 y columna 0
+            - La reina 1 esta en la fila 1 y columna 3
+            - La reina 2 esta en la fila 2 y columna 2
+            - La reina 3 esta en la fila 3 y columna 1
+            - Todos los valores son menores a N
+            - Los valores estan separados por ','
+"#
+            );
+
+            pedir_valor("Ingresa ahora el estado: ", &mut buff, |inp| {
+                let mut array = Vec::with_capacity(n);
+                inp.trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .split(',')
+                    .try_for_each(|val| {
+                        let res = val.trim().parse::<usize>();
+
+                        if let Ok(res) = res {
+                            if res >= n {
+                                Err(format!("Valor '{}' mayor o igual a N ({})", val, n))
+                            } else {
+                                array.push(res);
+                                Ok(())
+                            }
+                        } else {
+                            Err(format!("Valor '{}' no es un número válido", val))
+                        }
+                    })?;
+                if array.len() != n {
+                    Err("Not enough values. Fallbacking to random initial state".into())
+                } else {
+                    Ok(array)
+                }
+            })
+        });
+
+        let initial_state = match initial_state {
+            Some(Ok(state)) => Some(state),
+            Some(Err(err)) => {
+                println!("{err}");
+                None
+            }
+            None => None,
+        };
+
+        Ok(SolverOptions {
+            n,
+            verbose,
+            seed,
+            initial_state,
+            tie_strategy,
+            max_iterations: 10_000,
+            max_restarts: 10,
+            plateau_limit: 50,
+        })
+    }
+
+    /// Renderiza las opciones elegidas como un resumen legible de una línea.
+    pub fn describe(&self) -> String {
+        format!(
+            "N={} verbose={} seed={} estado_inicial={} ties={:?} max_iters={} max_restarts={} plateau_limit={}",
+            self.n,
+            self.verbose,
+            self.seed
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| "aleatoria".into()),
+            self.initial_state
+                .as_ref()
+                .map(|s| format!("{s:?}"))
+                .unwrap_or_else(|| "aleatorio".into()),
+            self.tie_strategy,
+            self.max_iterations,
+            self.max_restarts,
+            self.plateau_limit,
+        )
+    }
+
+    /// Construye el `NQueens` descrito por estas opciones.
+    pub fn build(&self) -> Result<NQueens, Cow<'static, str>> {
+        let nqueens = NQueens::new(self.n)
+            .ok_or("No se permiten valores de N menores a 4")?
+            .with_verbose(self.verbose)
+            .with_tie_strategy(self.tie_strategy)
+            .with_plateau_limit(self.plateau_limit);
+        let nqueens = match self.seed {
+            Some(seed) => nqueens.with_seed(seed),
+            None => nqueens,
+        };
+        match &self.initial_state {
+            Some(state) => nqueens
+                .with_state(state)
+                .ok_or("El estado inicial no tiene tamaño N".into()),
+            None => Ok(nqueens.into_random_state()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `count_solutions` debe coincidir con OEIS A000170 para N pequeñas,
+    /// que es justo la validación que motivó agregar el solver exacto.
+    #[test]
+    fn count_solutions_matches_oeis_a000170() {
+        let expected = [(4, 2), (5, 10), (6, 4), (7, 40), (8, 92)];
+        for (n, solutions) in expected {
+            assert_eq!(
+                NQueens::count_solutions(n),
+                solutions,
+                "count_solutions({n}) no coincide con OEIS A000170"
+            );
+        }
+    }
+
+    /// `count_solutions(0)` no debe entrar en pánico: hay exactamente una
+    /// forma de no colocar ninguna reina en un tablero vacío.
+    #[test]
+    fn count_solutions_zero_does_not_panic() {
+        assert_eq!(NQueens::count_solutions(0), 1);
+    }
+
+    /// Con la misma semilla, el mismo N y el mismo estado inicial, `step`
+    /// debe producir siempre la misma secuencia de costos: la reproducibilidad
+    /// que motivó agregar `with_seed`.
+    #[test]
+    fn same_seed_reproduces_same_step_sequence() {
+        let run = || {
+            let mut nqueens = NQueens::new(8).unwrap().with_seed(42).into_random_state();
+            (0..20).map(|_| nqueens.step()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(run(), run());
+    }
+
+    /// El costo que devuelve `step_tabu` (calculado en O(1) vía
+    /// `cost_after_move`/`overall_cost`) debe coincidir con una recomputación
+    /// desde cero del mismo tablero (`board_cost`), para varias iteraciones
+    /// seguidas.
+    #[test]
+    fn step_tabu_cost_matches_brute_force_recount() {
+        let mut nqueens = NQueens::new(10).unwrap().with_seed(7).into_random_state();
+        for _ in 0..50 {
+            let reported = nqueens.step_tabu();
+            assert_eq!(reported, NQueens::board_cost(&nqueens.queens));
+        }
+    }
+
+    /// `anneal` lleva el mejor costo visto en `self.best_cost`/`self.best_board`
+    /// mediante deltas O(1) (`cost_after_move`); debe coincidir con una
+    /// recomputación desde cero (`board_cost`) del mismo tablero.
+    #[test]
+    fn anneal_best_cost_matches_brute_force_recount() {
+        let mut nqueens = NQueens::new(10).unwrap().with_seed(7).into_random_state();
+        let config = SimulatedAnnealing {
+            t0: 10.0,
+            alpha: 0.9,
+            budget: AnnealingBudget::Iterations(300),
+        };
+
+        let reported = nqueens.anneal(&config);
+        let (best_board, best_cost) = nqueens.best();
+
+        assert_eq!(reported, best_cost);
+        assert_eq!(best_cost, NQueens::board_cost(best_board));
+    }
+
+    /// `board_cost` (usado por `LocalSearchProblem::cost`) se reescribió para
+    /// construir los conteos por grupo en O(n) en vez de recorrer todos los
+    /// pares de reinas en O(n²); debe seguir dando el mismo resultado que esa
+    /// cuenta de pares por fuerza bruta.
+    #[test]
+    fn board_cost_matches_naive_pair_count() {
+        fn naive_pair_count(board: &[usize]) -> usize {
+            let n = board.len();
+            let mut cost = 0;
+            for i in 0..n {
+                for j in (i + 1)..n {
+                    let same_col = board[i] == board[j];
+                    let same_diag = (i as isize - board[i] as isize)
+                        == (j as isize - board[j] as isize)
+                        || (i + board[i]) == (j + board[j]);
+                    if same_col || same_diag {
+                        cost += 2;
+                    }
+                }
+            }
+            cost
+        }
+
+        let boards: [&[usize]; 3] = [&[0, 4, 7, 5, 2, 6, 1, 3], &[0, 0, 0, 0], &[0, 1, 2, 3, 4]];
+        for board in boards {
+            assert_eq!(NQueens::board_cost(board), naive_pair_count(board));
+        }
+    }
+
+    /// `LocalSearchEngine::run`, genérico sobre `LocalSearchProblem`, debe
+    /// poder resolver N-Queens igual que `NQueens::run`, incluyendo el
+    /// reinicio por ciclo que usa el conjunto `seen` de estados visitados.
+    #[test]
+    fn local_search_engine_solves_nqueens() {
+        let nqueens = NQueens::new(8).unwrap();
+        let initial = nqueens.random_state(&mut StdRng::seed_from_u64(7));
+        let mut engine = LocalSearchEngine::new(nqueens, 20).with_seed(7);
+
+        let outcome = engine.run(initial, 10_000, 50);
+        assert!(matches!(outcome, SolveOutcome::Solved { .. }));
+    }
+
+    /// El costo de diagonales que devuelve `step_swap` (calculado en O(1) vía
+    /// `diagonal_cost_after_swap`/`group_delta`) debe coincidir con una
+    /// recomputación por fuerza bruta de los conflictos de diagonal del mismo
+    /// tablero, para varias iteraciones seguidas.
+    #[test]
+    fn step_swap_cost_matches_brute_force_recount() {
+        fn naive_diagonal_cost(board: &[usize]) -> usize {
+            let n = board.len();
+            let mut cost = 0;
+            for i in 0..n {
+                for j in (i + 1)..n {
+                    let same_diag = (i as isize - board[i] as isize)
+                        == (j as isize - board[j] as isize)
+                        || (i + board[i]) == (j + board[j]);
+                    if same_diag {
+                        cost += 2;
+                    }
+                }
+            }
+            cost
+        }
+
+        let mut nqueens = NQueens::new(10)
+            .unwrap()
+            .with_seed(7)
+            .into_random_permutation();
+        for _ in 0..50 {
+            let reported = nqueens.step_swap();
+            assert_eq!(reported, naive_diagonal_cost(&nqueens.queens));
+        }
+    }
+
+    /// Regresión para el bug donde `rebuild_counters` reescribía
+    /// `best_cost`/`best_board` en cada reinicio por meseta de `run`, dejando
+    /// `best()` apuntando al costo del último tablero aleatorio en vez del
+    /// mejor visto durante toda la corrida. Con un presupuesto de iteraciones
+    /// ajustado para forzar al menos un reinicio, el `board`/`best_cost` que
+    /// reporta `solve` deben seguir siendo consistentes entre sí.
+    #[test]
+    fn solve_best_cost_survives_plateau_restarts() {
+        // `plateau_limit(1)` fuerza un reinicio en cuanto `step` deje de
+        // mejorar estrictamente, lo que casi con certeza ocurre varias veces
+        // en 200 iteraciones sobre N=30, reproduciendo el escenario del bug.
+        let mut nqueens = NQueens::new(30)
+            .unwrap()
+            .with_seed(7)
+            .with_plateau_limit(1)
+            .into_random_state();
+
+        let report = nqueens.solve(200, 50);
+
+        assert!(
+            report.restarts > 0,
+            "la corrida debería haber reiniciado al menos una vez"
+        );
+        assert_eq!(report.best_cost, NQueens::board_cost(&report.board));
+        if !report.solved {
+            assert!(report.best_cost > 0);
+        }
+    }
+}